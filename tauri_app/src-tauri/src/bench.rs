@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::boerse::{fetch_candles, read_candles_csv};
+use crate::indicators::build_chart_response;
+use crate::models::IndicatorOptions;
+
+/// A reproducible benchmark workload loaded from JSON.
+#[derive(Debug, Deserialize)]
+pub struct Workload {
+    /// Default iteration count applied to items that don't override it.
+    #[serde(default = "default_iterations")]
+    pub iterations: usize,
+    pub items: Vec<WorkloadItem>,
+}
+
+/// A single request to exercise the fetch + indicator pipeline against.
+#[derive(Debug, Deserialize)]
+pub struct WorkloadItem {
+    pub identifier: String,
+    #[serde(rename = "rangeKey")]
+    pub range_key: String,
+    #[serde(default)]
+    pub indicators: IndicatorOptions,
+    /// Per-item iteration override.
+    #[serde(default)]
+    pub iterations: Option<usize>,
+    /// When set, candles are read from this CSV instead of fetched, so the
+    /// indicator stage can be measured deterministically without network.
+    #[serde(default, rename = "candlesCsv")]
+    pub candles_csv: Option<PathBuf>,
+}
+
+fn default_iterations() -> usize {
+    5
+}
+
+/// Latency distribution (milliseconds) for one pipeline stage.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageTimings {
+    pub samples: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// Benchmark result for a single workload item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemReport {
+    pub identifier: String,
+    #[serde(rename = "rangeKey")]
+    pub range_key: String,
+    pub offline: bool,
+    pub candles: usize,
+    pub fetch: Option<StageTimings>,
+    pub indicators: StageTimings,
+}
+
+/// Run a workload file and print the JSON report to stdout, optionally also
+/// writing a CSV report to `csv_path`.
+pub async fn run(workload_path: &Path, csv_path: Option<&Path>) -> Result<()> {
+    let raw = std::fs::read_to_string(workload_path)
+        .with_context(|| format!("Kann Workload {:?} nicht lesen", workload_path))?;
+    let workload: Workload =
+        serde_json::from_str(&raw).with_context(|| format!("Ungültiges Workload-JSON in {:?}", workload_path))?;
+
+    let client = Client::builder()
+        .user_agent("roppos-bench/1.0")
+        .build()
+        .context("Benchmark-HTTP-Client nicht initialisierbar")?;
+
+    let mut reports = Vec::new();
+    for item in &workload.items {
+        reports.push(run_item(&client, item, workload.iterations).await?);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+    if let Some(path) = csv_path {
+        std::fs::write(path, render_csv(&reports))
+            .with_context(|| format!("Kann CSV-Report {:?} nicht schreiben", path))?;
+    }
+    Ok(())
+}
+
+async fn run_item(client: &Client, item: &WorkloadItem, default_iterations: usize) -> Result<ItemReport> {
+    let iterations = item.iterations.unwrap_or(default_iterations).max(1);
+    let offline = item.candles_csv.is_some();
+
+    let mut fetch_samples = Vec::new();
+    let mut indicator_samples = Vec::new();
+    let mut candle_count = 0;
+
+    for _ in 0..iterations {
+        let candles = if let Some(path) = &item.candles_csv {
+            read_candles_csv(path)?
+        } else {
+            let start = Instant::now();
+            let candles = fetch_candles(client, &item.identifier, &item.range_key).await?;
+            fetch_samples.push(elapsed_ms(start));
+            candles
+        };
+        candle_count = candles.len();
+
+        let start = Instant::now();
+        let _ = build_chart_response(candles, &item.indicators)?;
+        indicator_samples.push(elapsed_ms(start));
+    }
+
+    Ok(ItemReport {
+        identifier: item.identifier.clone(),
+        range_key: item.range_key.clone(),
+        offline,
+        candles: candle_count,
+        fetch: summarise(&mut fetch_samples),
+        indicators: summarise(&mut indicator_samples)
+            .expect("indicator stage always runs at least once"),
+    })
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Summarise latency samples into min/median/p95/max, returning `None` when no
+/// samples were collected (e.g. the fetch stage in offline mode).
+fn summarise(samples: &mut [f64]) -> Option<StageTimings> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Some(StageTimings {
+        samples: samples.len(),
+        min: samples[0],
+        median: percentile(samples, 0.5),
+        p95: percentile(samples, 0.95),
+        max: samples[samples.len() - 1],
+    })
+}
+
+fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = quantile * (sorted.len() as f64 - 1.0);
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    if lower == upper {
+        sorted[lower]
+    } else {
+        let weight = rank - lower as f64;
+        sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+    }
+}
+
+fn render_csv(reports: &[ItemReport]) -> String {
+    let mut out = String::from(
+        "identifier,range_key,offline,candles,stage,samples,min_ms,median_ms,p95_ms,max_ms\n",
+    );
+    for report in reports {
+        if let Some(fetch) = &report.fetch {
+            push_csv_row(&mut out, report, "fetch", fetch);
+        }
+        push_csv_row(&mut out, report, "indicators", &report.indicators);
+    }
+    out
+}
+
+fn push_csv_row(out: &mut String, report: &ItemReport, stage: &str, timings: &StageTimings) {
+    out.push_str(&format!(
+        "{},{},{},{},{},{},{:.3},{:.3},{:.3},{:.3}\n",
+        report.identifier,
+        report.range_key,
+        report.offline,
+        report.candles,
+        stage,
+        timings.samples,
+        timings.min,
+        timings.median,
+        timings.p95,
+        timings.max,
+    ));
+}
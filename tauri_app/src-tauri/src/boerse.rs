@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
 use chrono_tz::Europe::Berlin;
 use once_cell::sync::Lazy;
 use reqwest::Client;
+use serde::Deserialize;
 use serde_json::Value;
 
 use crate::models::{CandleResponse, SearchResult};
@@ -176,11 +180,51 @@ fn parse_search_candidate(value: Value) -> Option<SearchResult> {
     }
 }
 
+/// Native candle interval (e.g. `"1m"`, `"1d"`) configured for a range key,
+/// used to key cached series.
+pub fn interval_for(range_key: &str) -> Option<&'static str> {
+    RANGE_OPTIONS.get(range_key).map(|option| option.interval)
+}
+
+/// Calendar span a range key covers (e.g. `"1 Jahr"` -> ~365 days), used to
+/// window reads from accumulated history so distinct range keys that share an
+/// interval (`"1 Jahr"`/`"3 Jahre"`/`"5 Jahre"` all key on `"1d"`) still show
+/// distinct windows once history has backfilled past their own span.
+pub fn span_for(range_key: &str) -> Option<Duration> {
+    let option = RANGE_OPTIONS.get(range_key)?;
+    Some(match option.range {
+        "1d" => Duration::days(1),
+        "5d" => Duration::days(5),
+        "1mo" => Duration::days(30),
+        "3mo" => Duration::days(90),
+        "6mo" => Duration::days(182),
+        "1y" => Duration::days(365),
+        "3y" => Duration::days(3 * 365),
+        "5y" => Duration::days(5 * 365),
+        other => unreachable!("unhandled range code: {other}"),
+    })
+}
+
 pub async fn fetch_candles(
     client: &Client,
     identifier: &str,
     range_key: &str,
 ) -> Result<Vec<CandleResponse>> {
+    Ok(fetch_candles_with_report(client, identifier, range_key)
+        .await?
+        .candles)
+}
+
+/// Fetch and validate a candle series like [`fetch_candles`], but also return
+/// the [`DataQualityReport`] so callers can surface data-quality issues in the
+/// UI instead of discarding the count.
+///
+/// Gaps are never filled here — see [`fill_session_gaps`] for the opt-in pass.
+pub async fn fetch_candles_with_report(
+    client: &Client,
+    identifier: &str,
+    range_key: &str,
+) -> Result<CleanedCandles> {
     let option = RANGE_OPTIONS
         .get(range_key)
         .ok_or_else(|| anyhow!("Unbekannte Range-Auswahl: {range_key}"))?;
@@ -221,7 +265,151 @@ pub async fn fetch_candles(
     }
 
     candles.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-    Ok(candles)
+    validate_and_clean(candles)
+}
+
+/// Data-quality counters produced by [`validate_and_clean`] and
+/// [`fill_session_gaps`], surfaced so the UI can flag how much the returned
+/// series was corrected.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct DataQualityReport {
+    pub rejected: usize,
+    pub duplicates_dropped: usize,
+    pub gaps_detected: usize,
+    pub fillers_inserted: usize,
+}
+
+/// A cleaned candle series together with the corrections applied to it.
+pub struct CleanedCandles {
+    pub candles: Vec<CandleResponse>,
+    pub report: DataQualityReport,
+}
+
+/// Run a consistency pass over a sorted candle series before it reaches the
+/// indicator engine.
+///
+/// Candles with non-finite OHLC, `high < low`, or a `close` outside
+/// `[low, high]` are rejected; exact-duplicate timestamps are collapsed
+/// keeping the last observation. This pass never fills gaps — see
+/// [`fill_session_gaps`] for the opt-in pass that does.
+pub fn validate_and_clean(candles: Vec<CandleResponse>) -> Result<CleanedCandles> {
+    let mut report = DataQualityReport::default();
+
+    let mut validated: Vec<CandleResponse> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        if !is_consistent(&candle) {
+            report.rejected += 1;
+            continue;
+        }
+        match validated.last() {
+            Some(last) if last.timestamp == candle.timestamp => {
+                report.duplicates_dropped += 1;
+                *validated.last_mut().expect("non-empty") = candle;
+            }
+            _ => validated.push(candle),
+        }
+    }
+
+    if validated.is_empty() {
+        return Err(anyhow!("Keine konsistenten Kursdaten nach Prüfung übrig"));
+    }
+
+    Ok(CleanedCandles {
+        candles: validated,
+        report,
+    })
+}
+
+/// Optionally insert flat filler candles into the gaps of an already-cleaned,
+/// sorted series so indicator windows in
+/// [`crate::indicators::build_chart_response`] don't jump across missing bars.
+///
+/// Only *intra-session* gaps are filled: an exact integer multiple of the
+/// modal spacing whose endpoints fall on the same Berlin-local calendar day.
+/// This deliberately excludes weekend/holiday gaps on daily series and
+/// overnight gaps on intraday series, which are real non-trading time, not
+/// missing data. Disabled by default; callers opt in explicitly (e.g. via
+/// `FetchChartRequest::fill_gaps`) because filled bars are synthetic and will
+/// flatten SMA/EMA/RSI/MACD/Bollinger across non-trading periods if misused.
+pub fn fill_session_gaps(candles: Vec<CandleResponse>, report: &mut DataQualityReport) -> Vec<CandleResponse> {
+    let spacing = match modal_spacing(&candles) {
+        Some(spacing) => spacing,
+        None => return candles,
+    };
+    fill_gaps(candles, spacing, report)
+}
+
+fn is_consistent(candle: &CandleResponse) -> bool {
+    let finite = candle.open.is_finite()
+        && candle.high.is_finite()
+        && candle.low.is_finite()
+        && candle.close.is_finite();
+    finite
+        && candle.high >= candle.low
+        && candle.close >= candle.low
+        && candle.close <= candle.high
+}
+
+fn modal_spacing(candles: &[CandleResponse]) -> Option<Duration> {
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for pair in candles.windows(2) {
+        let delta = (pair[1].timestamp - pair[0].timestamp).num_seconds();
+        if delta > 0 {
+            *counts.entry(delta).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(delta, _)| Duration::seconds(delta))
+}
+
+fn fill_gaps(
+    candles: Vec<CandleResponse>,
+    spacing: Duration,
+    report: &mut DataQualityReport,
+) -> Vec<CandleResponse> {
+    let step = spacing.num_seconds();
+    // A step of a full day or more means the series is daily/weekly; every gap
+    // at that spacing is a weekend or holiday, i.e. non-session time, so there
+    // is nothing intraday left to fill.
+    if step <= 0 || step >= Duration::days(1).num_seconds() {
+        return candles;
+    }
+    let mut filled: Vec<CandleResponse> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        if let Some(prev) = filled.last() {
+            let gap = (candle.timestamp - prev.timestamp).num_seconds();
+            // Only treat an exact integer multiple of the modal spacing as a
+            // gap, and only when it stays within the same Berlin-local
+            // trading day; anything crossing midnight is an overnight gap,
+            // not missing data.
+            let same_session = prev.timestamp_local.date_naive() == candle.timestamp_local.date_naive();
+            if gap > step && gap % step == 0 && same_session {
+                report.gaps_detected += 1;
+                let missing = (gap / step) - 1;
+                let prev_close = prev.close;
+                for k in 1..=missing {
+                    let ts = prev.timestamp + Duration::seconds(step * k);
+                    filled.push(flat_candle(ts.with_timezone(&Utc), prev_close));
+                    report.fillers_inserted += 1;
+                }
+            }
+        }
+        filled.push(candle);
+    }
+    filled
+}
+
+fn flat_candle(timestamp: DateTime<Utc>, price: f64) -> CandleResponse {
+    convert_raw_candle(RawCandle {
+        timestamp,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: None,
+    })
 }
 
 #[derive(Clone)]
@@ -326,6 +514,130 @@ fn parse_timestamp_str(text: &str) -> Option<DateTime<Utc>> {
         })
 }
 
+/// Where a candle series should come from when building a chart.
+///
+/// `Live` hits the Börse Stuttgart HTTP API; `Csv` loads a previously exported
+/// series from disk so `build_chart_response` can run against imported data
+/// identically to live data.
+#[derive(Debug, Clone)]
+pub enum CandleSource {
+    Live,
+    Csv(PathBuf),
+}
+
+impl Default for CandleSource {
+    fn default() -> Self {
+        CandleSource::Live
+    }
+}
+
+/// Load a candle series from the configured source, applying the same
+/// consistency pass as the live path.
+pub async fn load_candles(
+    client: &Client,
+    identifier: &str,
+    range_key: &str,
+    source: &CandleSource,
+) -> Result<Vec<CandleResponse>> {
+    match source {
+        CandleSource::Live => fetch_candles(client, identifier, range_key).await,
+        CandleSource::Csv(path) => {
+            let candles = read_candles_csv(path)?;
+            Ok(validate_and_clean(candles)?.candles)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CandleCsvRow {
+    timestamp: String,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    #[serde(default)]
+    volume: Option<f64>,
+}
+
+/// Deserialize a `timestamp,open,high,low,close,volume` CSV export into
+/// candles, tolerating the same timestamp formats as the live parser.
+pub fn read_candles_csv(path: &Path) -> Result<Vec<CandleResponse>> {
+    let file = File::open(path).with_context(|| format!("Kann CSV-Datei {:?} nicht öffnen", path))?;
+    let mut reader = csv::ReaderBuilder::new().has_headers(true).from_reader(file);
+    let mut candles = Vec::new();
+    for record in reader.deserialize::<CandleCsvRow>() {
+        let row = record.context("Ungültige CSV-Zeile in Kursdaten")?;
+        let timestamp = parse_timestamp_str(&row.timestamp)
+            .ok_or_else(|| anyhow!("Unlesbarer Zeitstempel: {}", row.timestamp))?;
+        candles.push(convert_raw_candle(RawCandle {
+            timestamp,
+            open: row.open,
+            high: row.high,
+            low: row.low,
+            close: row.close,
+            volume: row.volume,
+        }));
+    }
+    if candles.is_empty() {
+        return Err(anyhow!("CSV-Datei enthält keine Kursdaten"));
+    }
+    candles.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(candles)
+}
+
+/// Serialize a fetched candle series back to `timestamp,open,high,low,close,volume`
+/// CSV, writing the timestamp in RFC3339 and leaving volume blank when absent.
+pub fn write_candles_csv(candles: &[CandleResponse]) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new().from_writer(Cursor::new(Vec::new()));
+    writer.write_record(["timestamp", "open", "high", "low", "close", "volume"])?;
+    for candle in candles {
+        writer.write_record([
+            candle.timestamp.to_rfc3339(),
+            candle.open.to_string(),
+            candle.high.to_string(),
+            candle.low.to_string(),
+            candle.close.to_string(),
+            candle.volume.map(|v| v.to_string()).unwrap_or_default(),
+        ])?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| anyhow!("CSV-Serialisierung fehlgeschlagen: {err}"))?
+        .into_inner();
+    String::from_utf8(bytes).context("CSV-Ausgabe ist kein gültiges UTF-8")
+}
+
+/// Write a candle series to a CSV file at `path`.
+pub fn export_candles_csv(path: &Path, candles: &[CandleResponse]) -> Result<()> {
+    let csv = write_candles_csv(candles)?;
+    let mut file =
+        File::create(path).with_context(|| format!("Kann CSV-Datei {:?} nicht schreiben", path))?;
+    file.write_all(csv.as_bytes())
+        .with_context(|| format!("Schreiben nach {:?} fehlgeschlagen", path))?;
+    Ok(())
+}
+
+/// Build a [`CandleResponse`] from a UTC timestamp and OHLCV parts, applying
+/// the same Berlin-local derivation as the live parser. Used by the persistence
+/// layer when rehydrating cached rows.
+pub fn candle_from_utc(
+    timestamp: DateTime<Utc>,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: Option<f64>,
+) -> CandleResponse {
+    convert_raw_candle(RawCandle {
+        timestamp,
+        open,
+        high,
+        low,
+        close,
+        volume,
+    })
+}
+
 fn convert_raw_candle(raw: RawCandle) -> CandleResponse {
     let utc_offset = FixedOffset::east_opt(0).expect("UTC offset available");
     let ts_utc = raw.timestamp.with_timezone(&utc_offset);
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::boerse::DataQualityReport;
+use crate::candle_store::{fetch_and_cache, CandleStore};
+use crate::models::CandleResponse;
+
+const CACHE_FILENAME: &str = "candle_cache.json";
+
+/// A cached candle fetch: the series plus the Unix timestamp it was fetched at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub fetched_at: i64,
+    pub candles: Vec<CandleResponse>,
+    /// Data-quality report from the fetch that populated this entry. Absent
+    /// in cache files written before this field existed.
+    #[serde(default)]
+    pub report: DataQualityReport,
+}
+
+/// On-disk candle cache keyed by `(identifier, range_key)`, backed by a single
+/// JSON file next to the watchlist state.
+///
+/// Entries carry a per-range TTL so repeated chart views skip the HTTP round
+/// trip while fresh. Writes are atomic (temp file + rename) so a crash mid-write
+/// can't corrupt the cache, and a file that fails to parse degrades to an empty
+/// cache rather than failing the whole fetch.
+#[derive(Debug, Default)]
+pub struct CandleCache {
+    path: PathBuf,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl CandleCache {
+    /// Load the cache from `dir`, falling back to an empty cache if the file is
+    /// missing or unreadable.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(CACHE_FILENAME);
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    fn cache_key(identifier: &str, range_key: &str) -> String {
+        format!("{identifier}|{range_key}")
+    }
+
+    /// Return the cached candles and report for a key when an entry exists and
+    /// is still within its TTL.
+    pub fn get_fresh(
+        &self,
+        identifier: &str,
+        range_key: &str,
+    ) -> Option<(Vec<CandleResponse>, DataQualityReport)> {
+        let entry = self.entries.get(&Self::cache_key(identifier, range_key))?;
+        let age = Utc::now().timestamp() - entry.fetched_at;
+        if age >= 0 && Duration::seconds(age) < ttl_for(range_key) {
+            Some((entry.candles.clone(), entry.report))
+        } else {
+            None
+        }
+    }
+
+    /// Overwrite the entry for a key and persist the cache atomically.
+    pub fn store(
+        &mut self,
+        identifier: &str,
+        range_key: &str,
+        candles: Vec<CandleResponse>,
+        report: DataQualityReport,
+    ) -> Result<()> {
+        self.entries.insert(
+            Self::cache_key(identifier, range_key),
+            CacheEntry {
+                fetched_at: Utc::now().timestamp(),
+                candles,
+                report,
+            },
+        );
+        self.persist()
+    }
+
+    /// Drop every entry and persist the now-empty cache.
+    pub fn clear(&mut self) -> Result<()> {
+        self.entries.clear();
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<()> {
+        let serialised = serde_json::to_string(&self.entries)?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serialised)
+            .with_context(|| format!("Kann Cache-Temp-Datei {:?} nicht schreiben", tmp))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Kann Cache-Datei {:?} nicht ersetzen", self.path))?;
+        Ok(())
+    }
+}
+
+/// Per-range freshness window: intraday ranges expire in minutes, the longer
+/// daily/weekly ranges in hours.
+fn ttl_for(range_key: &str) -> Duration {
+    match range_key {
+        "1 Tag" | "5 Tage" => Duration::minutes(2),
+        "1 Monat" | "3 Monate" => Duration::minutes(10),
+        "6 Monate" => Duration::hours(1),
+        _ => Duration::hours(12),
+    }
+}
+
+/// Serve a candle fetch from the cache when fresh, otherwise backfill through
+/// the [`CandleStore`] (which fetches live, upserts, and returns the merged
+/// accumulated history), overwrite the JSON entry, and persist before
+/// returning.
+///
+/// Returns the candles together with the [`DataQualityReport`] from the fetch
+/// that produced them (a cache hit replays the stored report rather than
+/// discarding it).
+pub async fn get_or_fetch(
+    cache: &Mutex<CandleCache>,
+    store: &Mutex<CandleStore>,
+    client: &Client,
+    identifier: &str,
+    range_key: &str,
+) -> Result<(Vec<CandleResponse>, DataQualityReport)> {
+    if let Some(hit) = cache.lock().await.get_fresh(identifier, range_key) {
+        return Ok(hit);
+    }
+    let (fresh, report) = fetch_and_cache(store, client, identifier, range_key).await?;
+    cache
+        .lock()
+        .await
+        .store(identifier, range_key, fresh.clone(), report)?;
+    Ok((fresh, report))
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rusqlite::{params, Connection};
+use tokio::sync::Mutex;
+
+use crate::boerse::{self, candle_from_utc, fetch_candles_with_report, DataQualityReport};
+use crate::models::CandleResponse;
+
+/// SQLite-backed store that accumulates fetched candles so repeated chart views
+/// load fast and multi-year intraday history can be built up incrementally.
+///
+/// Rows are keyed by `(identifier, interval, timestamp)` so an upsert dedupes
+/// on timestamp. On each fetch the caller upserts the freshly fetched range and
+/// merges the stored and fresh series before indicators run; the fetch covers
+/// the whole range, not only the window after the newest stored candle.
+pub struct CandleStore {
+    conn: Connection,
+}
+
+impl CandleStore {
+    /// Open (creating if needed) the store at `path` and ensure the schema.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Kann Candle-Datenbank {:?} nicht öffnen", path))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS candles (
+                identifier TEXT NOT NULL,
+                interval   TEXT NOT NULL,
+                ts         INTEGER NOT NULL,
+                open       REAL NOT NULL,
+                high       REAL NOT NULL,
+                low        REAL NOT NULL,
+                close      REAL NOT NULL,
+                volume     REAL,
+                PRIMARY KEY (identifier, interval, ts)
+            )",
+            [],
+        )
+        .context("Kann Candle-Tabelle nicht anlegen")?;
+        Ok(Self { conn })
+    }
+
+    /// Insert or replace the given candles, deduping on timestamp. Returns the
+    /// number of rows written.
+    pub fn upsert_candles(
+        &self,
+        identifier: &str,
+        interval: &str,
+        candles: &[CandleResponse],
+    ) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO candles
+                    (identifier, interval, ts, open, high, low, close, volume)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for candle in candles {
+                stmt.execute(params![
+                    identifier,
+                    interval,
+                    candle.timestamp.timestamp(),
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    candle.volume,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(candles.len())
+    }
+
+    /// Load cached candles for `(identifier, interval)` within the inclusive
+    /// `[from, to]` window, ordered by timestamp.
+    pub fn load_cached(
+        &self,
+        identifier: &str,
+        interval: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<CandleResponse>> {
+        let from_ts = from.map(|dt| dt.timestamp()).unwrap_or(i64::MIN);
+        let to_ts = to.map(|dt| dt.timestamp()).unwrap_or(i64::MAX);
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, open, high, low, close, volume FROM candles
+             WHERE identifier = ?1 AND interval = ?2 AND ts >= ?3 AND ts <= ?4
+             ORDER BY ts ASC",
+        )?;
+        let rows = stmt.query_map(params![identifier, interval, from_ts, to_ts], |row| {
+            let ts: i64 = row.get(0)?;
+            Ok(candle_from_utc(
+                DateTime::<Utc>::from_timestamp(ts, 0).unwrap_or_else(Utc::now),
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })?;
+        let mut candles = Vec::new();
+        for candle in rows {
+            candles.push(candle?);
+        }
+        Ok(candles)
+    }
+
+    /// Newest stored timestamp for `(identifier, interval)`, if any.
+    pub fn newest_timestamp(
+        &self,
+        identifier: &str,
+        interval: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT MAX(ts) FROM candles WHERE identifier = ?1 AND interval = ?2",
+        )?;
+        let newest: Option<i64> =
+            stmt.query_row(params![identifier, interval], |row| row.get(0))?;
+        Ok(newest.and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0)))
+    }
+}
+
+/// Fetch a range live, upsert the validated fresh candles into the store, then
+/// return the merged stored + fresh series windowed to the requested range's
+/// span (and the fresh fetch's data-quality report) so indicators see the
+/// accumulated history the range key asked for, not everything ever stored.
+///
+/// The remote endpoint only serves whole ranges, so the incremental window is
+/// realised by deduping on upsert rather than by a narrowed HTTP request. The
+/// newest stored timestamp is not returned here; callers that want to log the
+/// gap can read it via [`CandleStore::newest_timestamp`].
+/// The fresh candles run through the same [`boerse::validate_and_clean`] pass
+/// as the live path before they ever reach the store, so merged history stays
+/// consistent with it.
+///
+/// The live fetch runs before `store` is locked, so the `Mutex<CandleStore>`
+/// guard is only held for the (fast, local) upsert + windowed read — concurrent
+/// batch fetches no longer serialize on the store lock for the HTTP round-trip.
+pub async fn fetch_and_cache(
+    store: &Mutex<CandleStore>,
+    client: &Client,
+    identifier: &str,
+    range_key: &str,
+) -> Result<(Vec<CandleResponse>, DataQualityReport)> {
+    let interval = boerse::interval_for(range_key)
+        .context("Unbekannte Range-Auswahl für Cache-Schlüssel")?;
+    let span = boerse::span_for(range_key).context("Unbekannte Range-Auswahl für Cache-Fenster")?;
+
+    let cleaned = fetch_candles_with_report(client, identifier, range_key).await?;
+
+    let store = store.lock().await;
+    store.upsert_candles(identifier, interval, &cleaned.candles)?;
+
+    // Window the read to the requested range's span so range keys that share
+    // an interval (e.g. "1 Jahr"/"3 Jahre"/"5 Jahre" all key on "1d") don't all
+    // return the same unbounded accumulated history.
+    let from = Utc::now() - span;
+    let merged = store.load_cached(identifier, interval, Some(from), None)?;
+    Ok((merged, cleaned.report))
+}
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use chrono::{Duration, NaiveTime};
 
+use crate::boerse::DataQualityReport;
 use crate::models::{
     BollingerSeries, CandleResponse, ChartResponse, IndicatorOptions, IndicatorSeries, MacdSeries,
-    OrbLevels,
+    OrbLevels, StochasticSeries,
 };
 
 const DEFAULT_BOLLINGER_PERIOD: usize = 20;
@@ -65,6 +66,29 @@ pub fn build_chart_response(
         None
     };
 
+    let atr = if options.show_atr {
+        Some(average_true_range(&candles, options.atr_period as usize))
+    } else {
+        None
+    };
+
+    let vwap = if options.show_vwap {
+        Some(volume_weighted_average_price(&candles))
+    } else {
+        None
+    };
+
+    let stochastic = if options.show_stochastic {
+        let (k, d) = stochastic_oscillator(
+            &candles,
+            options.stochastic_period as usize,
+            options.stochastic_smoothing as usize,
+        );
+        Some(StochasticSeries { k, d })
+    } else {
+        None
+    };
+
     let orb = compute_opening_range(&candles, options.orb_minutes as i64);
 
     Ok(ChartResponse {
@@ -75,7 +99,11 @@ pub fn build_chart_response(
         rsi,
         macd,
         volume,
+        atr,
+        vwap,
+        stochastic,
         orb,
+        data_quality: DataQualityReport::default(),
     })
 }
 
@@ -207,6 +235,90 @@ fn macd(values: &[f64], fast: usize, slow: usize, signal_period: usize) -> MacdS
     }
 }
 
+fn average_true_range(candles: &[CandleResponse], period: usize) -> Vec<Option<f64>> {
+    if period == 0 || candles.len() < period + 1 {
+        return vec![None; candles.len()];
+    }
+    let mut true_range = vec![0.0_f64; candles.len()];
+    true_range[0] = candles[0].high - candles[0].low;
+    for i in 1..candles.len() {
+        let prev_close = candles[i - 1].close;
+        true_range[i] = (candles[i].high - candles[i].low)
+            .max((candles[i].high - prev_close).abs())
+            .max((candles[i].low - prev_close).abs());
+    }
+    let mut atr = vec![None; candles.len()];
+    // Seed with the simple average of the first `period` true ranges that have
+    // a previous close (bars 1..=period), then Wilder-smooth like the RSI
+    // averaging above.
+    let seed: f64 = true_range[1..=period].iter().sum::<f64>() / period as f64;
+    let mut prev = seed;
+    atr[period] = Some(seed);
+    for i in (period + 1)..candles.len() {
+        prev = (prev * (period as f64 - 1.0) + true_range[i]) / period as f64;
+        atr[i] = Some(prev);
+    }
+    atr
+}
+
+fn volume_weighted_average_price(candles: &[CandleResponse]) -> Vec<Option<f64>> {
+    let mut vwap = vec![None; candles.len()];
+    let mut current_day = None;
+    let mut cumulative_pv = 0.0;
+    let mut cumulative_volume = 0.0;
+    for (i, candle) in candles.iter().enumerate() {
+        let day = candle.timestamp_local.date_naive();
+        if current_day != Some(day) {
+            current_day = Some(day);
+            cumulative_pv = 0.0;
+            cumulative_volume = 0.0;
+        }
+        let typical = (candle.high + candle.low + candle.close) / 3.0;
+        let volume = candle.volume.unwrap_or(0.0);
+        cumulative_pv += typical * volume;
+        cumulative_volume += volume;
+        if cumulative_volume > 0.0 {
+            vwap[i] = Some(cumulative_pv / cumulative_volume);
+        }
+    }
+    vwap
+}
+
+fn stochastic_oscillator(
+    candles: &[CandleResponse],
+    period: usize,
+    smoothing: usize,
+) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+    if period == 0 || period > candles.len() {
+        return (vec![None; candles.len()], vec![None; candles.len()]);
+    }
+    let mut k = vec![None; candles.len()];
+    for i in (period - 1)..candles.len() {
+        let window = &candles[i + 1 - period..=i];
+        let highest = window.iter().fold(f64::MIN, |acc, c| acc.max(c.high));
+        let lowest = window.iter().fold(f64::MAX, |acc, c| acc.min(c.low));
+        let range = highest - lowest;
+        if range > 0.0 {
+            k[i] = Some(100.0 * (candles[i].close - lowest) / range);
+        } else {
+            k[i] = Some(0.0);
+        }
+    }
+    // Smooth only the defined %K slice (rather than zero-filling the
+    // undefined prefix) so the first `smoothing` real %K values don't average
+    // in phantom zeros from the padding.
+    let defined_k: Vec<f64> = k[period - 1..]
+        .iter()
+        .map(|value| value.unwrap_or(0.0))
+        .collect();
+    let smoothed = simple_moving_average(&defined_k, smoothing);
+    let mut d = vec![None; candles.len()];
+    for (offset, value) in smoothed.into_iter().enumerate() {
+        d[period - 1 + offset] = value;
+    }
+    (k, d)
+}
+
 fn compute_opening_range(candles: &[CandleResponse], minutes: i64) -> Option<OrbLevels> {
     if candles.is_empty() {
         return None;
@@ -1,39 +1,75 @@
+mod bench;
 mod boerse;
+mod cache;
+mod candle_store;
 mod indicators;
 mod models;
+mod resolution;
 mod watchlist;
 
 use std::sync::Arc;
 
 use anyhow::Result;
-use boerse::{fetch_candles, search};
+use boerse::{fill_session_gaps, search};
+use cache::{get_or_fetch, CandleCache};
+use candle_store::CandleStore;
 use indicators::build_chart_response;
-use models::{ChartResponse, FetchChartRequest, Instrument, SearchResult};
-use reqwest::Client;
-use tauri::State;
-use tokio::sync::Mutex;
+use models::{CandleResponse, ChartResponse, FetchChartRequest, IndicatorOptions, Instrument, SearchResult};
+use reqwest::{Client, ClientBuilder};
+use resolution::Resolution;
+use tauri::api::path::home_dir;
+use tauri::http::ResponseBuilder;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::{Mutex, Semaphore};
 use watchlist::{
-    ensure_watchlist_dir, load_default_watchlist, load_user_watchlist, merge_watchlists,
-    persist_instrument,
+    ensure_watchlist_dir, export_watchlist as export_watchlist_file, import_watchlist as import_watchlist_file,
+    load_default_watchlist, load_user_watchlist, merge_watchlists, persist_instrument,
+    remove_instrument, reorder_watchlist as reorder_watchlist_entries, WatchlistFormat,
 };
 
+const STATE_DIR_NAME: &str = ".boerse_stuttgart_charts";
+
+type SharedCache = State<'_, Arc<Mutex<CandleCache>>>;
+type SharedStore = State<'_, Arc<Mutex<CandleStore>>>;
+
 struct HttpClientState {
     client: Client,
 }
 
 impl HttpClientState {
     fn new() -> Result<Self> {
-        let client = Client::builder()
+        let builder = Client::builder()
             .user_agent("Mozilla/5.0 (Tauri Desktop App)")
             .gzip(true)
             .brotli(true)
-            .deflate(true)
-            .build()?;
+            .deflate(true);
+        let client = configure_tls(builder).build()?;
         Ok(Self { client })
     }
 }
 
-type SharedClient = State<'_, Arc<Mutex<HttpClientState>>>;
+/// Select the TLS backend based on the enabled Cargo features.
+///
+/// The `default-tls` feature keeps reqwest's built-in default stack, while
+/// `rustls-tls-webpki-roots` / `rustls-tls-native-roots` switch to rustls so
+/// the app can be built on musl/static targets and in environments without the
+/// system OpenSSL. The two rustls features differ only in which root store
+/// reqwest is compiled against.
+fn configure_tls(builder: ClientBuilder) -> ClientBuilder {
+    #[cfg(any(
+        feature = "rustls-tls-webpki-roots",
+        feature = "rustls-tls-native-roots"
+    ))]
+    let builder = builder.use_rustls_tls();
+    builder
+}
+
+// The `reqwest::Client` is internally cloneable and `Send + Sync`, so it is
+// shared without an exclusive lock — concurrent fetches no longer serialize on
+// a single mutex.
+type SharedClient = State<'_, Arc<HttpClientState>>;
+
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
 
 #[tauri::command]
 async fn load_watchlist(app: tauri::AppHandle) -> Result<Vec<Instrument>, String> {
@@ -49,6 +85,42 @@ async fn add_to_watchlist(app: tauri::AppHandle, instrument: Instrument) -> Resu
     persist_instrument(&app, &cleaned).map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+async fn remove_from_watchlist(app: tauri::AppHandle, identifier: String) -> Result<(), String> {
+    remove_instrument(&app, &identifier).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+async fn reorder_watchlist(
+    app: tauri::AppHandle,
+    identifiers: Vec<String>,
+) -> Result<(), String> {
+    reorder_watchlist_entries(&app, &identifiers).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+async fn export_watchlist(app: tauri::AppHandle, format: String) -> Result<String, String> {
+    let format = WatchlistFormat::parse(&format).map_err(|err| err.to_string())?;
+    let base = load_default_watchlist().map_err(|err| err.to_string())?;
+    let custom = load_user_watchlist(&app).map_err(|err| err.to_string())?;
+    let merged = merge_watchlists(base, custom);
+    export_watchlist_file(&merged, format).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+async fn import_watchlist(
+    app: tauri::AppHandle,
+    path: String,
+    format: String,
+) -> Result<Vec<Instrument>, String> {
+    let format = WatchlistFormat::parse(&format).map_err(|err| err.to_string())?;
+    import_watchlist_file(&app, std::path::Path::new(&path), format)
+        .map_err(|err| err.to_string())?;
+    let base = load_default_watchlist().map_err(|err| err.to_string())?;
+    let custom = load_user_watchlist(&app).map_err(|err| err.to_string())?;
+    Ok(merge_watchlists(base, custom))
+}
+
 #[tauri::command]
 async fn search_instruments(
     state: SharedClient,
@@ -59,34 +131,370 @@ async fn search_instruments(
         return Err("Suchbegriff zu kurz".to_string());
     }
     let limit = limit.unwrap_or(15) as usize;
-    let guard = state.inner().lock().await;
-    search(&guard.client, &query, limit)
+    search(&state.inner().client, &query, limit)
         .await
         .map_err(|err| err.to_string())
 }
 
+/// Turn a fetched candle series and its [`boerse::DataQualityReport`] into a
+/// [`ChartResponse`], applying the request's opt-in gap fill and resample
+/// before indicators run and attaching the (possibly gap-fill-updated) report.
+fn finish_chart_response(
+    mut candles: Vec<CandleResponse>,
+    mut report: boerse::DataQualityReport,
+    request: &FetchChartRequest,
+) -> Result<ChartResponse, String> {
+    if request.fill_gaps {
+        candles = fill_session_gaps(candles, &mut report);
+    }
+    if let Some(label) = &request.resolution {
+        let target = Resolution::parse(label).map_err(|err| err.to_string())?;
+        candles = resolution::resample(&candles, target).map_err(|err| err.to_string())?;
+    }
+    let mut response =
+        build_chart_response(candles, &request.indicators).map_err(|err| err.to_string())?;
+    response.data_quality = report;
+    Ok(response)
+}
+
 #[tauri::command]
 async fn fetch_chart_data(
     state: SharedClient,
+    cache: SharedCache,
+    store: SharedStore,
     request: FetchChartRequest,
 ) -> Result<ChartResponse, String> {
-    let guard = state.inner().lock().await;
-    let candles = fetch_candles(&guard.client, &request.identifier, &request.range_key)
+    let (candles, report) = get_or_fetch(
+        cache.inner(),
+        store.inner(),
+        &state.inner().client,
+        &request.identifier,
+        &request.range_key,
+    )
+    .await
+    .map_err(|err| err.to_string())?;
+    finish_chart_response(candles, report, &request)
+}
+
+#[tauri::command]
+async fn fetch_charts_batch(
+    state: SharedClient,
+    cache: SharedCache,
+    store: SharedStore,
+    requests: Vec<FetchChartRequest>,
+    concurrency: Option<usize>,
+) -> Vec<Result<ChartResponse, String>> {
+    let client = state.inner().client.clone();
+    let cache = Arc::clone(cache.inner());
+    let store = Arc::clone(store.inner());
+    let limit = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY).max(1);
+    let semaphore = Arc::new(Semaphore::new(limit));
+
+    let mut handles = Vec::with_capacity(requests.len());
+    for request in requests {
+        let client = client.clone();
+        let cache = Arc::clone(&cache);
+        let store = Arc::clone(&store);
+        let semaphore = Arc::clone(&semaphore);
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("Semaphore bleibt offen");
+            let (candles, report) =
+                get_or_fetch(&cache, &store, &client, &request.identifier, &request.range_key)
+                    .await
+                    .map_err(|err| err.to_string())?;
+            finish_chart_response(candles, report, &request)
+        }));
+    }
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(join_err) => results.push(Err(join_err.to_string())),
+        }
+    }
+    results
+}
+
+#[tauri::command]
+async fn clear_cache(cache: SharedCache) -> Result<(), String> {
+    cache
+        .inner()
+        .lock()
         .await
-        .map_err(|err| err.to_string())?;
-    build_chart_response(candles, &request.indicators).map_err(|err| err.to_string())
+        .clear()
+        .map_err(|err| err.to_string())
+}
+
+/// Write a fetched chart's candle series to a CSV file so it can be archived or
+/// shared, reusing the same cache/backfill path as a normal chart fetch.
+#[tauri::command]
+async fn export_chart_csv(
+    state: SharedClient,
+    cache: SharedCache,
+    store: SharedStore,
+    request: FetchChartRequest,
+    path: String,
+) -> Result<(), String> {
+    let (candles, _report) = get_or_fetch(
+        cache.inner(),
+        store.inner(),
+        &state.inner().client,
+        &request.identifier,
+        &request.range_key,
+    )
+    .await
+    .map_err(|err| err.to_string())?;
+    boerse::export_candles_csv(std::path::Path::new(&path), &candles).map_err(|err| err.to_string())
+}
+
+/// Build a chart from a previously exported CSV series instead of the live
+/// API, so offline datasets run through the same indicator pipeline.
+#[tauri::command]
+async fn import_chart_csv(
+    state: SharedClient,
+    path: String,
+    indicators: IndicatorOptions,
+) -> Result<ChartResponse, String> {
+    let candles = boerse::load_candles(
+        &state.inner().client,
+        "",
+        "",
+        &boerse::CandleSource::Csv(std::path::PathBuf::from(path)),
+    )
+    .await
+    .map_err(|err| err.to_string())?;
+    build_chart_response(candles, &indicators).map_err(|err| err.to_string())
+}
+
+/// Build the `ChartResponse` for a `chart://<identifier>/<range_key>?indicators=...`
+/// request, reusing the shared client, cache and indicator pipeline. Serializes
+/// as plain JSON by default, or as [`ChartResponse::to_udf_bars`] columnar JSON
+/// when the URI carries `format=udf` (for TradingView-style UDF consumers).
+async fn serve_chart_uri(app: AppHandle, uri: String) -> Result<Vec<u8>, String> {
+    let (identifier, range_key, indicators, fill_gaps, resolution, udf_format) =
+        parse_chart_uri(&uri)?;
+
+    let client = app.state::<Arc<HttpClientState>>().client.clone();
+    let cache = app.state::<Arc<Mutex<CandleCache>>>();
+    let store = app.state::<Arc<Mutex<CandleStore>>>();
+
+    let (candles, report) =
+        get_or_fetch(cache.inner(), store.inner(), &client, &identifier, &range_key)
+            .await
+            .map_err(|err| err.to_string())?;
+    let request = FetchChartRequest {
+        identifier,
+        name: String::new(),
+        range_key,
+        indicators,
+        fill_gaps,
+        resolution,
+    };
+    let response = finish_chart_response(candles, report, &request)?;
+    if udf_format {
+        serde_json::to_vec(&response.to_udf_bars()).map_err(|err| err.to_string())
+    } else {
+        serde_json::to_vec(&response).map_err(|err| err.to_string())
+    }
+}
+
+/// Decompose a `chart://<identifier>/<range_key>?indicators=<json>&fill_gaps=<bool>&resolution=<label>&format=<json|udf>`
+/// URI into its parts. A missing or malformed `indicators` query yields the
+/// defaults; a missing `fill_gaps` defaults to `false`; a missing `resolution`
+/// leaves the series at its source interval; a missing or non-`udf` `format`
+/// serves the plain `ChartResponse` JSON.
+fn parse_chart_uri(
+    uri: &str,
+) -> Result<(String, String, IndicatorOptions, bool, Option<String>, bool), String> {
+    let rest = uri
+        .strip_prefix("chart://")
+        .ok_or_else(|| "Unerwartetes URI-Schema".to_string())?;
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (rest, None),
+    };
+    let (identifier, range_key) = path
+        .split_once('/')
+        .ok_or_else(|| "URI ohne Range-Auswahl".to_string())?;
+
+    let indicators = query
+        .and_then(|query| {
+            query.split('&').find_map(|pair| {
+                pair.strip_prefix("indicators=")
+                    .map(percent_decode)
+                    .and_then(|raw| serde_json::from_str::<IndicatorOptions>(&raw).ok())
+            })
+        })
+        .unwrap_or_default();
+
+    let fill_gaps = query
+        .and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("fill_gaps="))
+        })
+        .map(|raw| raw == "true" || raw == "1")
+        .unwrap_or(false);
+
+    let resolution = query.and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("resolution=").map(percent_decode))
+    });
+
+    let udf_format = query
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("format=")))
+        .map(|raw| raw == "udf")
+        .unwrap_or(false);
+
+    Ok((
+        percent_decode(identifier),
+        percent_decode(range_key),
+        indicators,
+        fill_gaps,
+        resolution,
+        udf_format,
+    ))
+}
+
+/// Minimal percent-decoding for URI path and query components (range keys such
+/// as `"1 Tag"` arrive as `1%20Tag`).
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hi = (bytes[i + 1] as char).to_digit(16);
+                let lo = (bytes[i + 2] as char).to_digit(16);
+                if let (Some(hi), Some(lo)) = (hi, lo) {
+                    out.push((hi * 16 + lo) as u8);
+                    i += 3;
+                    continue;
+                }
+                out.push(bytes[i]);
+                i += 1;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            other => {
+                out.push(other);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn run_bench_cli(args: Vec<String>) {
+    let mut args = args.into_iter();
+    let workload = match args.next() {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            eprintln!("Verwendung: roppos bench <workload.json> [report.csv]");
+            std::process::exit(2);
+        }
+    };
+    let csv = args.next().map(std::path::PathBuf::from);
+    let result = tauri::async_runtime::block_on(bench::run(&workload, csv.as_deref()));
+    if let Err(err) = result {
+        eprintln!("Benchmark fehlgeschlagen: {err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn load_candle_cache() -> CandleCache {
+    match home_dir() {
+        Some(home) => {
+            let dir = home.join(STATE_DIR_NAME);
+            let _ = std::fs::create_dir_all(&dir);
+            CandleCache::load(&dir)
+        }
+        None => CandleCache::default(),
+    }
+}
+
+const CANDLE_STORE_FILENAME: &str = "candles.sqlite";
+
+/// Open the SQLite backfill store next to the JSON candle cache, falling back
+/// to an in-memory database if the home directory can't be determined or the
+/// on-disk file can't be opened.
+fn load_candle_store() -> CandleStore {
+    let path = home_dir().map(|home| {
+        let dir = home.join(STATE_DIR_NAME);
+        let _ = std::fs::create_dir_all(&dir);
+        dir.join(CANDLE_STORE_FILENAME)
+    });
+    path.and_then(|path| CandleStore::open(&path).ok())
+        .or_else(|| CandleStore::open(std::path::Path::new(":memory:")).ok())
+        .expect("Candle-Datenbank (Datei oder :memory:) konnte geöffnet werden")
 }
 
 fn main() {
+    // `roppos bench <workload.json> [report.csv]` runs the benchmark harness
+    // instead of launching the desktop app, mirroring a `cargo xtask bench`.
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("bench") {
+        run_bench_cli(args.collect());
+        return;
+    }
+
     tauri::Builder::default()
-        .manage(Arc::new(Mutex::new(
+        .manage(Arc::new(
             HttpClientState::new().expect("HTTP-Client initialisierbar"),
-        )))
+        ))
+        .manage(Arc::new(Mutex::new(load_candle_cache())))
+        .manage(Arc::new(Mutex::new(load_candle_store())))
+        .register_asynchronous_uri_scheme_protocol("chart", |app, request, responder| {
+            let app = app.clone();
+            let uri = request.uri().to_string();
+            tauri::async_runtime::spawn(async move {
+                let response = match serve_chart_uri(app, uri).await {
+                    Ok(body) => ResponseBuilder::new()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .status(200)
+                        .body(body),
+                    Err(message) => ResponseBuilder::new()
+                        .header("Content-Type", "application/json")
+                        .status(500)
+                        .body(
+                            serde_json::json!({ "error": message })
+                                .to_string()
+                                .into_bytes(),
+                        ),
+                };
+                match response {
+                    Ok(response) => responder.respond(response),
+                    Err(err) => responder.respond(
+                        ResponseBuilder::new()
+                            .status(500)
+                            .body(err.to_string().into_bytes())
+                            .expect("Fehlerantwort ist konstruierbar"),
+                    ),
+                }
+            });
+        })
         .invoke_handler(tauri::generate_handler![
             load_watchlist,
             add_to_watchlist,
+            remove_from_watchlist,
+            reorder_watchlist,
+            export_watchlist,
+            import_watchlist,
             search_instruments,
-            fetch_chart_data
+            fetch_chart_data,
+            fetch_charts_batch,
+            clear_cache,
+            export_chart_csv,
+            import_chart_csv
         ])
         .run(tauri::generate_context!())
         .expect("Tauri-Anwendung konnte nicht gestartet werden");
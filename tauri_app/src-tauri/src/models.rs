@@ -1,6 +1,8 @@
 use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
+use crate::boerse::DataQualityReport;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instrument {
     #[serde(rename(deserialize = "Name", serialize = "name"), alias = "name")]
@@ -58,6 +60,18 @@ pub struct Instrument {
         default
     )]
     pub notes: Option<String>,
+    #[serde(
+        rename(deserialize = "Group", serialize = "group"),
+        alias = "group",
+        default
+    )]
+    pub group: Option<String>,
+    #[serde(
+        rename(deserialize = "Tags", serialize = "tags"),
+        alias = "tags",
+        default
+    )]
+    pub tags: Vec<String>,
 }
 
 impl Instrument {
@@ -70,6 +84,8 @@ impl Instrument {
         self.tp_management = self.tp_management.filter(|v| !v.trim().is_empty());
         self.time_window = self.time_window.filter(|v| !v.trim().is_empty());
         self.notes = self.notes.filter(|v| !v.trim().is_empty());
+        self.group = self.group.filter(|v| !v.trim().is_empty());
+        self.tags.retain(|tag| !tag.trim().is_empty());
         self
     }
 }
@@ -91,13 +107,57 @@ pub struct IndicatorOptions {
     pub show_volume: bool,
     #[serde(default = "default_orb_minutes")]
     pub orb_minutes: u32,
+    #[serde(default)]
+    pub show_atr: bool,
+    #[serde(default = "default_atr_period")]
+    pub atr_period: u32,
+    #[serde(default)]
+    pub show_vwap: bool,
+    #[serde(default)]
+    pub show_stochastic: bool,
+    #[serde(default = "default_stochastic_period")]
+    pub stochastic_period: u32,
+    #[serde(default = "default_stochastic_smoothing")]
+    pub stochastic_smoothing: u32,
 }
 
 fn default_orb_minutes() -> u32 {
     15
 }
 
-#[derive(Debug, Clone, Serialize)]
+fn default_atr_period() -> u32 {
+    14
+}
+
+fn default_stochastic_period() -> u32 {
+    14
+}
+
+fn default_stochastic_smoothing() -> u32 {
+    3
+}
+
+impl Default for IndicatorOptions {
+    fn default() -> Self {
+        Self {
+            sma_periods: Vec::new(),
+            ema_periods: Vec::new(),
+            show_bollinger: false,
+            show_rsi: false,
+            show_macd: false,
+            show_volume: false,
+            orb_minutes: default_orb_minutes(),
+            show_atr: false,
+            atr_period: default_atr_period(),
+            show_vwap: false,
+            show_stochastic: false,
+            stochastic_period: default_stochastic_period(),
+            stochastic_smoothing: default_stochastic_smoothing(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CandleResponse {
     pub timestamp: DateTime<FixedOffset>,
     pub timestamp_local: DateTime<FixedOffset>,
@@ -127,6 +187,12 @@ pub struct MacdSeries {
     pub histogram: Vec<Option<f64>>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct StochasticSeries {
+    pub k: Vec<Option<f64>>,
+    pub d: Vec<Option<f64>>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct OrbLevels {
     pub start_local: DateTime<FixedOffset>,
@@ -144,7 +210,188 @@ pub struct ChartResponse {
     pub rsi: Option<Vec<Option<f64>>>,
     pub macd: Option<MacdSeries>,
     pub volume: Option<Vec<Option<f64>>>,
+    pub atr: Option<Vec<Option<f64>>>,
+    pub vwap: Option<Vec<Option<f64>>>,
+    pub stochastic: Option<StochasticSeries>,
     pub orb: Option<OrbLevels>,
+    /// Corrections applied to the underlying candle series (rejects,
+    /// deduped duplicates, filled gaps) so the UI can surface data-quality
+    /// issues instead of silently rendering a corrected series.
+    pub data_quality: DataQualityReport,
+}
+
+/// A single scalar overlay (ORB level, latest moving-average value, …) carried
+/// alongside the columnar bars so a charting widget can render it without
+/// reshaping the per-series `Option` arrays.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChartMark {
+    pub label: String,
+    pub value: f64,
+}
+
+/// TradingView UDF-style columnar representation of a [`ChartResponse`].
+///
+/// `s` is `"ok"` when bars are present and `"no_data"` for an empty series;
+/// `t` holds the Unix epoch seconds of each candle's `timestamp`. Missing
+/// volume is reported as `0.0`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UdfBars {
+    pub s: String,
+    pub t: Vec<i64>,
+    pub o: Vec<f64>,
+    pub h: Vec<f64>,
+    pub l: Vec<f64>,
+    pub c: Vec<f64>,
+    pub v: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<ChartMark>,
+}
+
+impl ChartResponse {
+    /// Serialize the response into the columnar UDF bar format, gathering the
+    /// ORB levels and the latest moving-average / Bollinger values as marks.
+    pub fn to_udf_bars(&self) -> UdfBars {
+        if self.candles.is_empty() {
+            return UdfBars {
+                s: "no_data".to_string(),
+                t: Vec::new(),
+                o: Vec::new(),
+                h: Vec::new(),
+                l: Vec::new(),
+                c: Vec::new(),
+                v: Vec::new(),
+                marks: Vec::new(),
+            };
+        }
+
+        let len = self.candles.len();
+        let mut t = Vec::with_capacity(len);
+        let mut o = Vec::with_capacity(len);
+        let mut h = Vec::with_capacity(len);
+        let mut l = Vec::with_capacity(len);
+        let mut c = Vec::with_capacity(len);
+        let mut v = Vec::with_capacity(len);
+        for candle in &self.candles {
+            t.push(candle.timestamp.timestamp());
+            o.push(candle.open);
+            h.push(candle.high);
+            l.push(candle.low);
+            c.push(candle.close);
+            v.push(candle.volume.unwrap_or(0.0));
+        }
+
+        let mut marks = Vec::new();
+        if let Some(orb) = &self.orb {
+            marks.push(ChartMark {
+                label: "ORB High".to_string(),
+                value: orb.high,
+            });
+            marks.push(ChartMark {
+                label: "ORB Low".to_string(),
+                value: orb.low,
+            });
+        }
+        for series in &self.sma {
+            if let Some(value) = latest(&series.values) {
+                marks.push(ChartMark {
+                    label: series.name.clone(),
+                    value,
+                });
+            }
+        }
+        for series in &self.ema {
+            if let Some(value) = latest(&series.values) {
+                marks.push(ChartMark {
+                    label: series.name.clone(),
+                    value,
+                });
+            }
+        }
+        if let Some(bollinger) = &self.bollinger {
+            if let Some(value) = latest(&bollinger.upper) {
+                marks.push(ChartMark {
+                    label: "Bollinger Upper".to_string(),
+                    value,
+                });
+            }
+            if let Some(value) = latest(&bollinger.lower) {
+                marks.push(ChartMark {
+                    label: "Bollinger Lower".to_string(),
+                    value,
+                });
+            }
+        }
+
+        UdfBars {
+            s: "ok".to_string(),
+            t,
+            o,
+            h,
+            l,
+            c,
+            v,
+            marks,
+        }
+    }
+}
+
+/// Most recent present value in an indicator series, scanning from the end.
+fn latest(values: &[Option<f64>]) -> Option<f64> {
+    values.iter().rev().find_map(|value| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boerse::candle_from_utc;
+    use chrono::{TimeZone, Utc};
+
+    fn empty_response() -> ChartResponse {
+        ChartResponse {
+            candles: Vec::new(),
+            sma: Vec::new(),
+            ema: Vec::new(),
+            bollinger: None,
+            rsi: None,
+            macd: None,
+            volume: None,
+            atr: None,
+            vwap: None,
+            stochastic: None,
+            orb: None,
+            data_quality: DataQualityReport::default(),
+        }
+    }
+
+    #[test]
+    fn to_udf_bars_reports_no_data_for_empty_series() {
+        let bars = empty_response().to_udf_bars();
+        assert_eq!(bars.s, "no_data");
+        assert!(bars.t.is_empty());
+    }
+
+    #[test]
+    fn to_udf_bars_maps_columns_and_marks() {
+        let ts = Utc.with_ymd_and_hms(2026, 1, 5, 9, 0, 0).unwrap();
+        let mut response = empty_response();
+        response.candles = vec![candle_from_utc(ts, 10.0, 11.0, 9.0, 10.5, Some(42.0))];
+        response.sma = vec![IndicatorSeries {
+            name: "SMA 20".to_string(),
+            values: vec![Some(10.2)],
+        }];
+
+        let bars = response.to_udf_bars();
+        assert_eq!(bars.s, "ok");
+        assert_eq!(bars.t, vec![ts.timestamp()]);
+        assert_eq!(bars.o, vec![10.0]);
+        assert_eq!(bars.h, vec![11.0]);
+        assert_eq!(bars.l, vec![9.0]);
+        assert_eq!(bars.c, vec![10.5]);
+        assert_eq!(bars.v, vec![42.0]);
+        assert_eq!(bars.marks.len(), 1);
+        assert_eq!(bars.marks[0].label, "SMA 20");
+        assert_eq!(bars.marks[0].value, 10.2);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,4 +409,14 @@ pub struct FetchChartRequest {
     #[serde(rename = "rangeKey")]
     pub range_key: String,
     pub indicators: IndicatorOptions,
+    /// Opt-in: insert flat filler candles into intra-session gaps before
+    /// indicators run (see [`crate::boerse::fill_session_gaps`]). Off by
+    /// default so charts reflect the real, unfilled series.
+    #[serde(default)]
+    pub fill_gaps: bool,
+    /// Optional coarser timeframe to resample the fetched series into before
+    /// indicators run (see [`crate::resolution::Resolution::parse`] for the
+    /// accepted labels). `None` leaves the series at its source interval.
+    #[serde(default)]
+    pub resolution: Option<String>,
 }
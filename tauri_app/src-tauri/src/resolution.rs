@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+
+use crate::models::CandleResponse;
+
+/// Candle timeframe used when resampling a finer series into a coarser one.
+///
+/// The remote endpoints only hand back one fixed interval per range, so this
+/// enum lets callers pick any coarser bucket client-side (see [`resample`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinute,
+    FifteenMinute,
+    OneHour,
+    FourHour,
+    OneDay,
+    OneWeek,
+}
+
+impl Resolution {
+    /// Parse a short timeframe label (`"1m"`, `"5m"`, `"15m"`, `"1h"`, `"4h"`,
+    /// `"1d"`, `"1w"`) as accepted on [`crate::models::FetchChartRequest::resolution`].
+    pub fn parse(label: &str) -> Result<Self> {
+        match label {
+            "1m" => Ok(Resolution::OneMinute),
+            "5m" => Ok(Resolution::FiveMinute),
+            "15m" => Ok(Resolution::FifteenMinute),
+            "1h" => Ok(Resolution::OneHour),
+            "4h" => Ok(Resolution::FourHour),
+            "1d" => Ok(Resolution::OneDay),
+            "1w" => Ok(Resolution::OneWeek),
+            other => Err(anyhow!("Unbekannte Ziel-Auflösung: {other}")),
+        }
+    }
+
+    /// Approximate length of the timeframe in minutes, used to order
+    /// resolutions and to reject resampling to a finer target.
+    pub fn approx_minutes(self) -> i64 {
+        match self {
+            Resolution::OneMinute => 1,
+            Resolution::FiveMinute => 5,
+            Resolution::FifteenMinute => 15,
+            Resolution::OneHour => 60,
+            Resolution::FourHour => 240,
+            Resolution::OneDay => 1_440,
+            Resolution::OneWeek => 10_080,
+        }
+    }
+
+    /// Identity of the bucket a candle belongs to at this resolution.
+    ///
+    /// Intraday resolutions floor the Berlin-local time to a multiple of the
+    /// timeframe measured from local midnight, so buckets line up with the
+    /// trading day. Daily and weekly resolutions key on the Berlin calendar
+    /// date / ISO week rather than UTC, so day boundaries match the exchange.
+    fn bucket_key(self, local: &DateTime<FixedOffset>) -> (i32, u32, i64) {
+        match self {
+            Resolution::OneDay => {
+                let date = local.date_naive();
+                (date.year(), date.ordinal(), 0)
+            }
+            Resolution::OneWeek => {
+                let iso = local.iso_week();
+                (iso.year(), iso.week(), 0)
+            }
+            _ => {
+                let date = local.date_naive();
+                let minutes_since_midnight =
+                    i64::from(local.hour()) * 60 + i64::from(local.minute());
+                let index = minutes_since_midnight / self.approx_minutes();
+                (date.year(), date.ordinal(), index)
+            }
+        }
+    }
+}
+
+/// Aggregate a finer candle series into the coarser `to` resolution.
+///
+/// Each output candle takes the first candle's `open`, the last candle's
+/// `close`, the max/min `high`/`low` across the bucket, and the summed
+/// `volume` (`None` when every input in the bucket is `None`). The input is
+/// assumed sorted by `timestamp`; output buckets stay sorted. Resampling to a
+/// resolution finer than the source interval is rejected.
+pub fn resample(candles: &[CandleResponse], to: Resolution) -> Result<Vec<CandleResponse>> {
+    if candles.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let source = source_interval_minutes(candles);
+    if to.approx_minutes() < source {
+        return Err(anyhow!(
+            "Resampling auf ein feineres Intervall ist nicht möglich (Quelle ~{source}min, Ziel {}min)",
+            to.approx_minutes()
+        ));
+    }
+
+    let mut out: Vec<CandleResponse> = Vec::new();
+    let mut current_key: Option<(i32, u32, i64)> = None;
+    let mut volume_seen = false;
+
+    for candle in candles {
+        let key = to.bucket_key(&candle.timestamp_local);
+        if current_key == Some(key) {
+            let bucket = out
+                .last_mut()
+                .expect("bucket exists once current_key is set");
+            bucket.high = bucket.high.max(candle.high);
+            bucket.low = bucket.low.min(candle.low);
+            bucket.close = candle.close;
+            if let Some(v) = candle.volume {
+                bucket.volume = Some(bucket.volume.unwrap_or(0.0) + v);
+                volume_seen = true;
+            }
+        } else {
+            current_key = Some(key);
+            volume_seen = candle.volume.is_some();
+            out.push(candle.clone());
+        }
+        // A bucket whose inputs were all `None` keeps `None` volume rather than
+        // reporting `0.0`.
+        if !volume_seen {
+            if let Some(bucket) = out.last_mut() {
+                bucket.volume = None;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Modal spacing between consecutive candles, in minutes, used to guard
+/// against upward-only aggregation. Falls back to one minute when the series
+/// is too short to infer a spacing.
+fn source_interval_minutes(candles: &[CandleResponse]) -> i64 {
+    use std::collections::HashMap;
+
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for pair in candles.windows(2) {
+        let delta = (pair[1].timestamp - pair[0].timestamp).num_minutes();
+        if delta > 0 {
+            *counts.entry(delta).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(delta, _)| delta)
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boerse::candle_from_utc;
+    use chrono::{TimeZone, Utc};
+
+    fn minute_candle(minute: u32, open: f64, high: f64, low: f64, close: f64) -> CandleResponse {
+        let ts = Utc.with_ymd_and_hms(2026, 1, 5, 9, minute, 0).unwrap();
+        candle_from_utc(ts, open, high, low, close, Some(1.0))
+    }
+
+    #[test]
+    fn resample_aggregates_bucket_ohlc_and_volume() {
+        let candles = vec![
+            minute_candle(0, 10.0, 11.0, 9.0, 10.5),
+            minute_candle(1, 10.5, 12.0, 10.0, 11.0),
+            minute_candle(2, 11.0, 11.5, 10.5, 11.2),
+            minute_candle(5, 11.2, 11.8, 11.0, 11.5),
+        ];
+
+        let resampled = resample(&candles, Resolution::FiveMinute).unwrap();
+
+        assert_eq!(resampled.len(), 2);
+        let first = &resampled[0];
+        assert_eq!(first.open, 10.0);
+        assert_eq!(first.close, 11.2);
+        assert_eq!(first.high, 12.0);
+        assert_eq!(first.low, 9.0);
+        assert_eq!(first.volume, Some(3.0));
+
+        let second = &resampled[1];
+        assert_eq!(second.open, 11.2);
+        assert_eq!(second.close, 11.5);
+        assert_eq!(second.volume, Some(1.0));
+    }
+
+    #[test]
+    fn resample_rejects_finer_target() {
+        let candles = vec![minute_candle(0, 1.0, 1.0, 1.0, 1.0), minute_candle(5, 1.0, 1.0, 1.0, 1.0)];
+        assert!(resample(&candles, Resolution::OneMinute).is_err());
+    }
+
+    #[test]
+    fn resample_empty_series_is_empty() {
+        assert!(resample(&[], Resolution::OneDay).unwrap().is_empty());
+    }
+}
@@ -1,9 +1,10 @@
 use std::fs;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
 use csv::ReaderBuilder;
+use serde::Deserialize;
 use serde_json::Value;
 use tauri::api::path::home_dir;
 use tauri::AppHandle;
@@ -14,17 +15,82 @@ const DEFAULT_WATCHLIST: &str = include_str!("../../../data/watchlist.csv");
 const STATE_DIR_NAME: &str = ".boerse_stuttgart_charts";
 const USER_FILENAME: &str = "custom_watchlist.json";
 
-pub fn load_default_watchlist() -> Result<Vec<Instrument>> {
-    let cursor = Cursor::new(DEFAULT_WATCHLIST.as_bytes());
-    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(cursor);
+/// CSV row shape for an [`Instrument`].
+///
+/// `tags` stays a single `String` column here because `csv`'s serde support
+/// maps one CSV column to one field and can't split a joined `"a;b"` column
+/// into `Instrument::tags: Vec<String>` on its own; [`read_instruments_csv`]
+/// does that split explicitly after deserializing the row.
+#[derive(Debug, Deserialize)]
+struct InstrumentCsvRow {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Identifier")]
+    identifier: String,
+    #[serde(rename = "Market", default)]
+    market: Option<String>,
+    #[serde(rename = "Cluster", default)]
+    cluster: Option<String>,
+    #[serde(rename = "Primary Triggers", default)]
+    primary_triggers: Option<String>,
+    #[serde(rename = "Entry Setup", default)]
+    entry_setup: Option<String>,
+    #[serde(rename = "Stop Rule", default)]
+    stop_rule: Option<String>,
+    #[serde(rename = "TP/Management", default)]
+    tp_management: Option<String>,
+    #[serde(rename = "Time Window (CEST)", default)]
+    time_window: Option<String>,
+    #[serde(rename = "Notes", default)]
+    notes: Option<String>,
+    #[serde(rename = "Group", default)]
+    group: Option<String>,
+    #[serde(rename = "Tags", default)]
+    tags: String,
+}
+
+impl From<InstrumentCsvRow> for Instrument {
+    fn from(row: InstrumentCsvRow) -> Self {
+        Instrument {
+            name: row.name,
+            identifier: row.identifier,
+            market: row.market,
+            cluster: row.cluster,
+            primary_triggers: row.primary_triggers,
+            entry_setup: row.entry_setup,
+            stop_rule: row.stop_rule,
+            tp_management: row.tp_management,
+            time_window: row.time_window,
+            notes: row.notes,
+            group: row.group,
+            tags: row
+                .tags
+                .split(';')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Deserialize a `watchlist.csv`-shaped reader into [`Instrument`]s, splitting
+/// the joined `Tags` column back into a `Vec<String>` so export followed by
+/// import reproduces the original instruments.
+fn read_instruments_csv<R: std::io::Read>(reader: R) -> Result<Vec<Instrument>> {
+    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(reader);
     let mut instruments = Vec::new();
-    for record in reader.deserialize::<Instrument>() {
-        let instrument = record.map(|inst| inst.normalised())?;
-        instruments.push(instrument);
+    for record in reader.deserialize::<InstrumentCsvRow>() {
+        let instrument: Instrument = record?.into();
+        instruments.push(instrument.normalised());
     }
     Ok(instruments)
 }
 
+pub fn load_default_watchlist() -> Result<Vec<Instrument>> {
+    read_instruments_csv(Cursor::new(DEFAULT_WATCHLIST.as_bytes()))
+}
+
 pub fn ensure_watchlist_dir(_handle: &AppHandle) -> Result<PathBuf> {
     let home = home_dir().ok_or_else(|| anyhow!("Konnte Benutzerverzeichnis nicht bestimmen"))?;
     let watchlist_dir = home.join(STATE_DIR_NAME);
@@ -73,9 +139,144 @@ pub fn persist_instrument(handle: &AppHandle, instrument: &Instrument) -> Result
     Ok(())
 }
 
-pub fn merge_watchlists(mut base: Vec<Instrument>, mut custom: Vec<Instrument>) -> Vec<Instrument> {
-    base.sort_by(|a, b| a.name.cmp(&b.name));
-    custom.sort_by(|a, b| a.name.cmp(&b.name));
+/// Overwrite the user watchlist file with `instruments`, preserving the order
+/// given. Used by the CRUD and import paths that replace the whole list.
+pub fn write_user_watchlist(handle: &AppHandle, instruments: &[Instrument]) -> Result<()> {
+    let path = user_watchlist_path(handle)?;
+    let serialised = serde_json::to_string_pretty(instruments)?;
+    fs::write(&path, serialised)
+        .with_context(|| format!("Kann Watchlist-Datei {:?} nicht schreiben", path))?;
+    Ok(())
+}
+
+/// Remove the entry with `identifier` from the user watchlist. Entries that
+/// only exist in the bundled defaults are left untouched.
+pub fn remove_instrument(handle: &AppHandle, identifier: &str) -> Result<()> {
+    let mut existing = load_user_watchlist(handle)?;
+    existing.retain(|entry| entry.identifier != identifier);
+    write_user_watchlist(handle, &existing)
+}
+
+/// Reorder the user watchlist to match `identifiers`; entries not named in the
+/// list keep their relative order at the end.
+pub fn reorder_watchlist(handle: &AppHandle, identifiers: &[String]) -> Result<()> {
+    let existing = load_user_watchlist(handle)?;
+    let mut ordered = Vec::with_capacity(existing.len());
+    for identifier in identifiers {
+        if let Some(found) = existing.iter().find(|entry| &entry.identifier == identifier) {
+            ordered.push(found.clone());
+        }
+    }
+    for entry in &existing {
+        if !ordered.iter().any(|e| e.identifier == entry.identifier) {
+            ordered.push(entry.clone());
+        }
+    }
+    write_user_watchlist(handle, &ordered)
+}
+
+/// Serialization formats supported by import/export.
+#[derive(Debug, Clone, Copy)]
+pub enum WatchlistFormat {
+    Csv,
+    Json,
+    Yaml,
+}
+
+impl WatchlistFormat {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(WatchlistFormat::Csv),
+            "json" => Ok(WatchlistFormat::Json),
+            "yaml" | "yml" => Ok(WatchlistFormat::Yaml),
+            other => Err(anyhow!("Unbekanntes Watchlist-Format: {other}")),
+        }
+    }
+}
+
+const CSV_HEADERS: [&str; 12] = [
+    "Name",
+    "Identifier",
+    "Market",
+    "Cluster",
+    "Primary Triggers",
+    "Entry Setup",
+    "Stop Rule",
+    "TP/Management",
+    "Time Window (CEST)",
+    "Notes",
+    "Group",
+    "Tags",
+];
+
+/// Serialize the given instruments into the requested format. CSV follows the
+/// bundled `watchlist.csv` schema so a round-trip reproduces the same
+/// instruments.
+pub fn export_watchlist(instruments: &[Instrument], format: WatchlistFormat) -> Result<String> {
+    match format {
+        WatchlistFormat::Json => {
+            Ok(serde_json::to_string_pretty(instruments).context("JSON-Export fehlgeschlagen")?)
+        }
+        WatchlistFormat::Yaml => {
+            Ok(serde_yaml::to_string(instruments).context("YAML-Export fehlgeschlagen")?)
+        }
+        WatchlistFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new().from_writer(Vec::new());
+            writer.write_record(CSV_HEADERS)?;
+            for instrument in instruments {
+                writer.write_record([
+                    instrument.name.clone(),
+                    instrument.identifier.clone(),
+                    instrument.market.clone().unwrap_or_default(),
+                    instrument.cluster.clone().unwrap_or_default(),
+                    instrument.primary_triggers.clone().unwrap_or_default(),
+                    instrument.entry_setup.clone().unwrap_or_default(),
+                    instrument.stop_rule.clone().unwrap_or_default(),
+                    instrument.tp_management.clone().unwrap_or_default(),
+                    instrument.time_window.clone().unwrap_or_default(),
+                    instrument.notes.clone().unwrap_or_default(),
+                    instrument.group.clone().unwrap_or_default(),
+                    instrument.tags.join(";"),
+                ])?;
+            }
+            let bytes = writer
+                .into_inner()
+                .map_err(|err| anyhow!("CSV-Serialisierung fehlgeschlagen: {err}"))?;
+            String::from_utf8(bytes).context("CSV-Ausgabe ist kein gültiges UTF-8")
+        }
+    }
+}
+
+/// Parse an exported watchlist file and merge it into the user watchlist,
+/// reusing [`merge_watchlists`] dedup semantics so re-importing a previously
+/// exported file is idempotent.
+pub fn import_watchlist(handle: &AppHandle, path: &Path, format: WatchlistFormat) -> Result<()> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Kann Import-Datei {:?} nicht lesen", path))?;
+    let imported: Vec<Instrument> = match format {
+        WatchlistFormat::Json => serde_json::from_str(&raw).context("Ungültiges JSON beim Import")?,
+        WatchlistFormat::Yaml => serde_yaml::from_str(&raw).context("Ungültiges YAML beim Import")?,
+        WatchlistFormat::Csv => read_instruments_csv(Cursor::new(raw.into_bytes()))?,
+    };
+    let imported: Vec<Instrument> = imported
+        .into_iter()
+        .map(|instrument| instrument.normalised())
+        .collect();
+
+    let existing = load_user_watchlist(handle)?;
+    let merged = merge_watchlists(existing, imported);
+    write_user_watchlist(handle, &merged)
+}
+
+/// Merge `custom` into `base`, keeping `base`'s order and appending any
+/// `custom` entries it doesn't already contain (by `identifier`) in their
+/// given order.
+///
+/// This deliberately does not sort: callers rely on the input order surviving
+/// the merge, e.g. [`reorder_watchlist`] writing a custom order that
+/// [`crate::load_watchlist`] must reproduce, and `import_watchlist` relying on
+/// a stable merge to stay idempotent on re-import.
+pub fn merge_watchlists(mut base: Vec<Instrument>, custom: Vec<Instrument>) -> Vec<Instrument> {
     for instrument in custom.into_iter() {
         if base
             .iter()
@@ -85,6 +286,5 @@ pub fn merge_watchlists(mut base: Vec<Instrument>, mut custom: Vec<Instrument>)
         }
         base.push(instrument);
     }
-    base.sort_by(|a, b| a.name.cmp(&b.name));
     base
 }